@@ -0,0 +1,107 @@
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    JsonSchema,
+};
+use serde_json::Value;
+
+/// The structured shape a provider is asked to produce.
+#[derive(Debug, serde::Deserialize, JsonSchema)]
+pub struct Commit {
+    /// The title of the commit.
+    pub title: String,
+
+    /// An exhaustive description of the changes.
+    pub description: String,
+}
+
+impl std::fmt::Display for Commit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n\n{}", self.title, self.description)
+    }
+}
+
+impl Commit {
+    /// The JSON Schema for this type, handed to providers that support
+    /// structured output / function calling so they return something we
+    /// can deserialize directly instead of free-form prose.
+    pub fn json_schema() -> Value {
+        let settings = SchemaSettings::draft07();
+        let generator = SchemaGenerator::new(settings);
+        let mut schema =
+            serde_json::to_value(generator.into_root_schema_for::<Commit>()).unwrap_or_default();
+        strict_schema(&mut schema);
+        schema
+    }
+
+    /// Deserializes `text` as a `Commit`. Providers that ignore the schema
+    /// (or models that don't support structured output) just return prose,
+    /// so on parse failure we fall back to treating it as a raw message.
+    pub fn parse_or_raw(text: &str) -> Commit {
+        serde_json::from_str(text).unwrap_or_else(|_| Commit {
+            title: text.to_string(),
+            description: String::new(),
+        })
+    }
+}
+
+/// One logical commit out of a `--split` run: the staged paths it covers
+/// plus the message to use for it.
+#[derive(Debug, serde::Deserialize, JsonSchema)]
+pub struct CommitGroup {
+    /// Paths (relative to the repo root) that belong in this commit.
+    pub paths: Vec<String>,
+
+    /// The commit this group of changes should produce.
+    pub commit: Commit,
+}
+
+/// A provider's proposed breakdown of a large staged diff into several
+/// semantic commits.
+#[derive(Debug, serde::Deserialize, JsonSchema)]
+pub struct SplitPlan {
+    /// The proposed commits, in the order they should be created.
+    pub groups: Vec<CommitGroup>,
+}
+
+impl SplitPlan {
+    pub fn json_schema() -> Value {
+        let settings = SchemaSettings::draft07();
+        let generator = SchemaGenerator::new(settings);
+        let mut schema =
+            serde_json::to_value(generator.into_root_schema_for::<SplitPlan>()).unwrap_or_default();
+        strict_schema(&mut schema);
+        schema
+    }
+
+    pub fn parse(text: &str) -> Result<SplitPlan, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+}
+
+/// Recursively rewrites a schemars-generated schema in place so it satisfies
+/// OpenAI's structured-output "strict" mode: every object schema gets
+/// `additionalProperties: false`, and `required` is widened to list every
+/// property (schemars only fills it in for non-`Option` fields).
+fn strict_schema(schema: &mut Value) {
+    if let Some(object) = schema.as_object_mut() {
+        if object.contains_key("properties") {
+            object.insert("additionalProperties".to_string(), Value::Bool(false));
+
+            if let Some(properties) = object.get("properties").and_then(Value::as_object) {
+                let required: Vec<Value> = properties
+                    .keys()
+                    .map(|key| Value::String(key.clone()))
+                    .collect();
+                object.insert("required".to_string(), Value::Array(required));
+            }
+        }
+
+        for value in object.values_mut() {
+            strict_schema(value);
+        }
+    } else if let Some(array) = schema.as_array_mut() {
+        for value in array {
+            strict_schema(value);
+        }
+    }
+}
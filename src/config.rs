@@ -0,0 +1,202 @@
+use log::warn;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_API_BASE_URL: &str = "https://sai-library.saiapplications.com/api";
+const DEFAULT_TEMPLATE_ID: &str = "66b12119075c349831386040";
+const DEFAULT_LANGUAGE_FALLBACK: &str = "pt-BR";
+const DEFAULT_API_KEY_ENV: &str = "SAI_API_KEY";
+const DEFAULT_PROVIDERS: &[&str] = &["sai"];
+const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini";
+const DEFAULT_OPENAI_API_KEY_ENV: &str = "OPENAI_API_KEY";
+const DEFAULT_SENDMAIL_BINARY: &str = "sendmail";
+const DEFAULT_FORGE_BASE_URL: &str = "https://api.github.com";
+const DEFAULT_FORGE_TOKEN_ENV: &str = "GITHUB_TOKEN";
+const DEFAULT_FORGE_MODE: &str = "pr";
+const DEFAULT_FORGE_REMOTE: &str = "origin";
+const DEFAULT_FORGE_BASE_BRANCH: &str = "main";
+
+/// User-facing configuration, loaded from `~/.config/auto-commit/config.toml`
+/// (or the path given via `--config`). CLI flags take priority over these
+/// values, which in turn take priority over the built-in defaults above.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub api_base_url: Option<String>,
+    pub template_id: Option<String>,
+    pub language: Option<String>,
+    pub default_dry_run: Option<bool>,
+    pub disable_spinner: Option<bool>,
+    /// Name of the environment variable holding the API key, in case the
+    /// default `SAI_API_KEY` clashes with something else on the user's
+    /// machine.
+    pub api_key_env: Option<String>,
+
+    /// Ordered list of providers to try, e.g. `["sai", "openai"]`. The first
+    /// one to succeed wins.
+    pub providers: Option<Vec<String>>,
+
+    pub openai_base_url: Option<String>,
+    pub openai_model: Option<String>,
+    pub openai_api_key_env: Option<String>,
+
+    /// Settings used by `--notify` to email the commit out once it's made.
+    #[serde(default)]
+    pub notify: NotifyConfig,
+
+    /// Settings used by `--open-pr` to report the commit to a forge.
+    #[serde(default)]
+    pub forge: ForgeConfig,
+}
+
+/// Who to email the generated commit to, and how.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    pub recipients: Vec<String>,
+    pub from: Option<String>,
+
+    /// `"sendmail"` (default) or `"http"`.
+    pub transport: Option<String>,
+
+    /// Binary invoked as `<sendmail_binary> -t` with the RFC822 message on
+    /// stdin, when `transport = "sendmail"`.
+    pub sendmail_binary: Option<String>,
+
+    /// URL of an SMTP-relay HTTP endpoint the RFC822 message is POSTed to,
+    /// when `transport = "http"`.
+    pub http_url: Option<String>,
+}
+
+impl NotifyConfig {
+    pub fn sendmail_binary(&self) -> &str {
+        self.sendmail_binary
+            .as_deref()
+            .unwrap_or(DEFAULT_SENDMAIL_BINARY)
+    }
+}
+
+/// Which forge (GitHub/Gitea/Forgejo) to report the generated commit to.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ForgeConfig {
+    /// API base URL, e.g. `https://api.github.com` or a Gitea/Forgejo
+    /// instance's `https://<host>/api/v1`.
+    pub base_url: Option<String>,
+
+    /// Name of the environment variable holding the forge access token.
+    pub token_env: Option<String>,
+
+    pub owner: Option<String>,
+    pub repo: Option<String>,
+
+    /// Remote to push the current branch to before reporting. Defaults to
+    /// `"origin"`.
+    pub remote: Option<String>,
+
+    /// Branch PRs are opened against. Defaults to `"main"`.
+    pub base_branch: Option<String>,
+
+    /// `"pr"` (open a draft PR, default) or `"status"` (post a commit status).
+    pub mode: Option<String>,
+}
+
+impl ForgeConfig {
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_deref().unwrap_or(DEFAULT_FORGE_BASE_URL)
+    }
+
+    pub fn token_env(&self) -> &str {
+        self.token_env.as_deref().unwrap_or(DEFAULT_FORGE_TOKEN_ENV)
+    }
+
+    pub fn mode(&self) -> &str {
+        self.mode.as_deref().unwrap_or(DEFAULT_FORGE_MODE)
+    }
+
+    pub fn remote(&self) -> &str {
+        self.remote.as_deref().unwrap_or(DEFAULT_FORGE_REMOTE)
+    }
+
+    pub fn base_branch(&self) -> &str {
+        self.base_branch
+            .as_deref()
+            .unwrap_or(DEFAULT_FORGE_BASE_BRANCH)
+    }
+}
+
+impl Config {
+    /// Loads the config from `path` if given, otherwise from the default
+    /// per-user location. Missing or unparsable files fall back to
+    /// `Config::default()` rather than failing the whole run.
+    pub fn load(path: Option<&Path>) -> Self {
+        let path = path.map(Path::to_path_buf).or_else(default_config_path);
+
+        let Some(path) = path else {
+            return Self::default();
+        };
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => return Self::default(),
+        };
+
+        toml::from_str(&contents).unwrap_or_else(|err| {
+            warn!("Couldn't parse config at {}: {err}", path.display());
+            Self::default()
+        })
+    }
+
+    pub fn api_base_url(&self) -> &str {
+        self.api_base_url.as_deref().unwrap_or(DEFAULT_API_BASE_URL)
+    }
+
+    pub fn template_id(&self) -> &str {
+        self.template_id.as_deref().unwrap_or(DEFAULT_TEMPLATE_ID)
+    }
+
+    pub fn language_fallback(&self) -> &str {
+        self.language
+            .as_deref()
+            .unwrap_or(DEFAULT_LANGUAGE_FALLBACK)
+    }
+
+    pub fn api_key_env(&self) -> &str {
+        self.api_key_env.as_deref().unwrap_or(DEFAULT_API_KEY_ENV)
+    }
+
+    pub fn execute_url(&self) -> String {
+        format!(
+            "{}/templates/{}/execute",
+            self.api_base_url(),
+            self.template_id()
+        )
+    }
+
+    pub fn providers(&self) -> Vec<String> {
+        self.providers.clone().unwrap_or_else(|| {
+            DEFAULT_PROVIDERS.iter().map(|s| s.to_string()).collect()
+        })
+    }
+
+    pub fn openai_base_url(&self) -> &str {
+        self.openai_base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_OPENAI_BASE_URL)
+    }
+
+    pub fn openai_model(&self) -> &str {
+        self.openai_model.as_deref().unwrap_or(DEFAULT_OPENAI_MODEL)
+    }
+
+    pub fn openai_api_key_env(&self) -> &str {
+        self.openai_api_key_env
+            .as_deref()
+            .unwrap_or(DEFAULT_OPENAI_API_KEY_ENV)
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("auto-commit").join("config.toml"))
+}
@@ -0,0 +1,97 @@
+use crate::config::ForgeConfig;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+/// Reports a generated commit upstream to a GitHub/Gitea/Forgejo forge,
+/// either as a commit status or a draft pull request. Gated behind
+/// `--open-pr` so existing behavior is unchanged otherwise.
+pub struct Forge<'a> {
+    client: &'a Client,
+    base_url: &'a str,
+    token: String,
+    owner: &'a str,
+    repo: &'a str,
+    mode: &'a str,
+    base_branch: &'a str,
+}
+
+impl<'a> Forge<'a> {
+    /// Builds a forge client from config. Returns `None` if `owner`/`repo`
+    /// aren't set (nothing to report to), or `Some(Err(..))` if they are
+    /// set but the token environment variable is missing.
+    pub fn from_config(client: &'a Client, config: &'a ForgeConfig) -> Option<Result<Self, String>> {
+        let owner = config.owner.as_deref()?;
+        let repo = config.repo.as_deref()?;
+
+        let token = match std::env::var(config.token_env()) {
+            Ok(token) => token,
+            Err(_) => return Some(Err(format!("{} is not set", config.token_env()))),
+        };
+
+        Some(Ok(Self {
+            client,
+            base_url: config.base_url(),
+            token,
+            owner,
+            repo,
+            mode: config.mode(),
+            base_branch: config.base_branch(),
+        }))
+    }
+
+    /// Reports the commit on `branch` (`sha` is its full hash), returning
+    /// the URL of the created status or PR.
+    pub async fn report(&self, branch: &str, sha: &str, title: &str, body: &str) -> Result<String, String> {
+        match self.mode {
+            "status" => self.post_status(sha, title).await,
+            _ => self.open_draft_pr(branch, title, body).await,
+        }
+    }
+
+    async fn post_status(&self, sha: &str, description: &str) -> Result<String, String> {
+        let url = format!(
+            "{}/repos/{}/{}/statuses/{}",
+            self.base_url, self.owner, self.repo, sha
+        );
+
+        let body = json!({
+            "state": "success",
+            "description": description,
+            "context": "auto-commit",
+        });
+
+        let payload = self.post(&url, &body).await?;
+        Ok(payload["url"].as_str().unwrap_or(&url).to_string())
+    }
+
+    async fn open_draft_pr(&self, branch: &str, title: &str, body: &str) -> Result<String, String> {
+        let url = format!("{}/repos/{}/{}/pulls", self.base_url, self.owner, self.repo);
+
+        let request_body = json!({
+            "title": title,
+            "body": body,
+            "head": branch,
+            "base": self.base_branch,
+            "draft": true,
+        });
+
+        let payload = self.post(&url, &request_body).await?;
+        Ok(payload["html_url"].as_str().unwrap_or(&url).to_string())
+    }
+
+    async fn post(&self, url: &str, body: &Value) -> Result<Value, String> {
+        self.client
+            .post(url)
+            .bearer_auth(&self.token)
+            .header("User-Agent", "auto-commit")
+            .json(body)
+            .send()
+            .await
+            .map_err(|err| err.to_string())?
+            .error_for_status()
+            .map_err(|err| err.to_string())?
+            .json()
+            .await
+            .map_err(|err| err.to_string())
+    }
+}
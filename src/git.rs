@@ -0,0 +1,269 @@
+use git2::{Repository, Signature};
+use thiserror::Error;
+
+/// Errors that can occur while talking to the repository through `git2`.
+#[derive(Debug, Error)]
+pub enum GitError {
+    #[error("not inside a git repository")]
+    NotARepo,
+
+    #[error("there are no staged files to commit")]
+    NothingStaged,
+
+    #[error(transparent)]
+    Git2(#[from] git2::Error),
+}
+
+/// Thin wrapper around a `git2::Repository`, giving the rest of the app
+/// structured access to the object database instead of shelling out to the
+/// `git` binary.
+pub struct GitBackend {
+    repo: Repository,
+}
+
+impl GitBackend {
+    /// Opens the repository discovered from the current directory.
+    pub fn discover() -> Result<Self, GitError> {
+        let repo = Repository::discover(".").map_err(|_| GitError::NotARepo)?;
+
+        if repo.workdir().is_none() {
+            return Err(GitError::NotARepo);
+        }
+
+        Ok(Self { repo })
+    }
+
+    /// The staged diff (index vs `HEAD`), equivalent to `git diff --staged`.
+    pub fn staged_diff(&self) -> Result<String, GitError> {
+        let head_tree = self.repo.head().and_then(|head| head.peel_to_tree()).ok();
+        let diff =
+            self.repo
+                .diff_tree_to_index(head_tree.as_ref(), None, None)?;
+
+        let text = diff_to_string(&diff)?;
+        if text.is_empty() {
+            return Err(GitError::NothingStaged);
+        }
+
+        Ok(text)
+    }
+
+    /// The full diff against `HEAD` (staged + unstaged), equivalent to
+    /// `git diff HEAD`.
+    pub fn diff_head(&self) -> Result<String, GitError> {
+        let head_tree = self.repo.head().and_then(|head| head.peel_to_tree()).ok();
+        let diff = self
+            .repo
+            .diff_tree_to_workdir_with_index(head_tree.as_ref(), None)?;
+
+        diff_to_string(&diff)
+    }
+
+    /// Paths with staged changes, relative to the repo root.
+    pub fn staged_files(&self) -> Result<Vec<String>, GitError> {
+        let head_tree = self.repo.head().and_then(|head| head.peel_to_tree()).ok();
+        let diff = self.repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+
+        let mut paths = Vec::new();
+        diff.foreach(
+            &mut |delta, _| {
+                if let Some(path) = delta.new_file().path() {
+                    paths.push(path.to_string_lossy().to_string());
+                }
+                true
+            },
+            None,
+            None,
+            None,
+        )?;
+
+        Ok(paths)
+    }
+
+    /// Replaces the index with only `paths`, reset against `HEAD` first.
+    /// Used by `--split` to stage one proposed commit group at a time.
+    pub fn restage_only(&self, paths: &[String]) -> Result<(), GitError> {
+        let mut index = self.repo.index()?;
+
+        match self.repo.head().and_then(|head| head.peel_to_tree()) {
+            Ok(tree) => index.read_tree(&tree)?,
+            Err(_) => index.clear()?,
+        }
+
+        for path in paths {
+            let path = std::path::Path::new(path);
+            if self.repo.workdir().map(|dir| dir.join(path)).is_some_and(|p| p.exists()) {
+                index.add_path(path)?;
+            } else {
+                index.remove_path(path)?;
+            }
+        }
+
+        index.write()?;
+        Ok(())
+    }
+
+    /// The name and email that would be used to author the next commit,
+    /// formatted as `Name <email>`.
+    pub fn author(&self) -> Result<String, GitError> {
+        let signature = self.signature()?;
+        Ok(format!(
+            "{} <{}>",
+            signature.name().unwrap_or("unknown"),
+            signature.email().unwrap_or("unknown")
+        ))
+    }
+
+    /// Creates a commit from the current index, with `HEAD` (if any) as its
+    /// sole parent, and returns the new commit's full hash.
+    pub fn commit(&self, message: &str) -> Result<String, GitError> {
+        let mut index = self.repo.index()?;
+        let tree_oid = index.write_tree()?;
+        let tree = self.repo.find_tree(tree_oid)?;
+
+        let signature = self.signature()?;
+
+        let parents = match self.repo.head() {
+            Ok(head) => vec![head.peel_to_commit()?],
+            Err(_) => vec![],
+        };
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+        let commit_oid = self.repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parent_refs,
+        )?;
+
+        Ok(commit_oid.to_string())
+    }
+
+    /// The signature used to author commits: the repo/global `user.name`
+    /// and `user.email`, falling back to a generic identity if neither is
+    /// configured.
+    fn signature(&self) -> Result<Signature<'static>, GitError> {
+        Ok(self
+            .repo
+            .signature()
+            .or_else(|_| Signature::now("Auto Commit", "auto-commit@localhost"))?)
+    }
+
+    /// The name of the currently checked-out branch, if `HEAD` isn't detached.
+    pub fn current_branch(&self) -> Result<String, GitError> {
+        let head = self.repo.head()?;
+        head.shorthand()
+            .map(str::to_string)
+            .map_err(|_| GitError::NotARepo)
+    }
+
+    /// Pushes the current branch to `remote`, using the same credential
+    /// helpers (`ssh-agent`, credential helper, `GIT_*` env vars) the `git`
+    /// CLI would use.
+    pub fn push_current_branch(&self, remote: &str) -> Result<String, GitError> {
+        let branch = self.current_branch()?;
+        let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+
+        let mut remote = self.repo.find_remote(remote)?;
+
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| {
+            git2::Cred::credential_helper(&git2::Config::open_default()?, url, username)
+                .or_else(|_| git2::Cred::ssh_key_from_agent(username.unwrap_or("git")))
+                .or_else(|_| {
+                    if allowed.contains(git2::CredentialType::DEFAULT) {
+                        git2::Cred::default()
+                    } else {
+                        Err(git2::Error::from_str("no usable git credentials found"))
+                    }
+                })
+        });
+
+        let mut options = git2::PushOptions::new();
+        options.remote_callbacks(callbacks);
+
+        remote.push(&[refspec], Some(&mut options))?;
+
+        Ok(branch)
+    }
+}
+
+fn diff_to_string(diff: &git2::Diff) -> Result<String, GitError> {
+    let mut out = String::new();
+    diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            out.push(line.origin());
+        }
+        out.push_str(std::str::from_utf8(line.content()).unwrap_or_default());
+        true
+    })?;
+    Ok(out)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty repo with an initial commit containing `file` at `contents`.
+    fn repo_with_initial_commit(file: &str, contents: &str) -> (tempfile::TempDir, GitBackend) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join(file), contents).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree_oid = index.write_tree().unwrap();
+
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        {
+            let tree = repo.find_tree(tree_oid).unwrap();
+            repo.commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[])
+                .unwrap();
+        }
+
+        (dir, GitBackend { repo })
+    }
+
+    #[test]
+    fn diff_to_string_prefixes_content_lines_not_headers() {
+        let (dir, backend) = repo_with_initial_commit("file.txt", "one\ntwo\nthree\n");
+
+        std::fs::write(dir.path().join("file.txt"), "one\nTWO\nthree\n").unwrap();
+        let mut index = backend.repo.index().unwrap();
+        index.add_path(std::path::Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+
+        let diff = backend.staged_diff().unwrap();
+
+        assert!(diff.contains("diff --git a/file.txt b/file.txt"));
+        assert!(!diff.contains("Fdiff --git"));
+        assert!(diff.contains("@@ -1,3 +1,3 @@"));
+        assert!(!diff.contains("H@@"));
+        assert!(diff.contains("-two\n"));
+        assert!(diff.contains("+TWO\n"));
+    }
+
+    #[test]
+    fn restage_only_handles_deleted_files() {
+        let (dir, backend) = repo_with_initial_commit("keep.txt", "kept\n");
+
+        std::fs::write(dir.path().join("added.txt"), "added\n").unwrap();
+        std::fs::remove_file(dir.path().join("keep.txt")).unwrap();
+
+        backend
+            .restage_only(&["keep.txt".to_string(), "added.txt".to_string()])
+            .unwrap();
+
+        let staged = backend.staged_files().unwrap();
+        assert!(staged.contains(&"keep.txt".to_string()));
+        assert!(staged.contains(&"added.txt".to_string()));
+
+        let diff = backend.staged_diff().unwrap();
+        assert!(diff.contains("deleted file mode"));
+    }
+}
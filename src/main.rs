@@ -1,19 +1,26 @@
+mod commit;
+mod config;
+mod forge;
+mod git;
+mod notify;
+mod providers;
+mod split;
+
 use clap::Parser;
 use clap_verbosity_flag::{InfoLevel, Verbosity};
-use log::{error, info};
+use config::Config;
+use forge::Forge;
+use git::GitBackend;
+use log::{error, info, warn};
+use notify::Notifier;
+use providers::{OpenAiProvider, Provider, SaiProvider};
+use std::path::PathBuf;
 use sys_locale::get_locale;
 use question::{Answer, Question};
 use rand::seq::SliceRandom;
-use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::Client;
-use schemars::{
-    gen::{SchemaGenerator, SchemaSettings},
-    JsonSchema,
-};
-use serde_json::json;
 use spinners::{Spinner, Spinners};
 use std::{
-    io::Write,
     process::{Command, Stdio},
     str,
 };
@@ -42,21 +49,30 @@ struct Cli {
 
     #[arg(short, long, help = "Don't ask for confirmation before committing.")]
     force: bool,
-}
 
-#[derive(Debug, serde::Deserialize, JsonSchema)]
-struct Commit {
-    /// The title of the commit.
-    title: String,
+    #[arg(
+        long,
+        help = "Path to a config file. Defaults to ~/.config/auto-commit/config.toml."
+    )]
+    config: Option<PathBuf>,
 
-    /// An exhaustive description of the changes.
-    description: String,
-}
+    #[arg(
+        long,
+        help = "Split the staged diff into several semantic commits instead of one."
+    )]
+    split: bool,
 
-impl ToString for Commit {
-    fn to_string(&self) -> String {
-        format!("{}\n\n{}", self.title, self.description)
-    }
+    #[arg(
+        long,
+        help = "Email the generated commit to the recipients configured under [notify]."
+    )]
+    notify: bool,
+
+    #[arg(
+        long = "open-pr",
+        help = "Push the branch and report the commit to the forge configured under [forge]."
+    )]
+    open_pr: bool,
 }
 
 #[tokio::main]
@@ -66,52 +82,39 @@ async fn main() -> Result<(), ()> {
         .filter_level(cli.verbose.log_level_filter())
         .init();
 
-    let api_token = std::env::var("SAI_API_KEY").unwrap_or_else(|_| {
-        error!("Please set the SAI_API_KEY environment variable.");
-        std::process::exit(1);
-    });
-
-    let git_staged_cmd = Command::new("git")
-        .arg("diff")
-        .arg("--staged")
-        .output()
-        .expect("Couldn't find diff.")
-        .stdout;
+    let config = Config::load(cli.config.as_deref());
 
-    let git_staged_cmd = str::from_utf8(&git_staged_cmd).unwrap();
+    let dry_run = cli.dry_run || config.default_dry_run.unwrap_or(false);
+    let disable_spinner = config.disable_spinner.unwrap_or(false);
 
-    if git_staged_cmd.is_empty() {
-        error!("There are no staged files to commit.\nTry running `git add` to stage some files.");
+    let client = Client::new();
+    let providers = build_providers(&config, &client);
+    if providers.is_empty() {
+        error!("No usable providers are configured. Set the relevant API key environment variable(s) and try again.");
         std::process::exit(1);
     }
 
-    let is_repo = Command::new("git")
-        .arg("rev-parse")
-        .arg("--is-inside-work-tree")
-        .output()
-        .expect("Failed to check if this is a git repository.")
-        .stdout;
-
-    if str::from_utf8(&is_repo).unwrap().trim() != "true" {
+    let backend = GitBackend::discover().unwrap_or_else(|_| {
         error!("It looks like you are not in a git repository.\nPlease run this command from the root of a git repository, or initialize one using `git init`.");
         std::process::exit(1);
-    }
+    });
 
-    let client = Client::new();
+    let _staged = backend.staged_diff().unwrap_or_else(|err| {
+        error!("{}\nTry running `git add` to stage some files.", err);
+        std::process::exit(1);
+    });
 
-    let output = Command::new("git")
-        .arg("diff")
-        .arg("HEAD")
-        .output()
-        .expect("Couldn't find diff.")
-        .stdout;
-    let output = str::from_utf8(&output).unwrap();
+    let output = backend.diff_head().unwrap_or_else(|err| {
+        error!("Couldn't compute the diff: {}", err);
+        std::process::exit(1);
+    });
+    let output = output.as_str();
 
-    if !cli.dry_run {
+    if !dry_run {
         info!("Loading Data...");
     }
 
-    let sp: Option<Spinner> = if !cli.dry_run && cli.verbose.is_silent() {
+    let sp: Option<Spinner> = if !dry_run && !disable_spinner && cli.verbose.is_silent() {
         let vs = [
             Spinners::Earth,
             Spinners::Aesthetic,
@@ -146,34 +149,61 @@ async fn main() -> Result<(), ()> {
         None
     };
 
-    let mut headers = HeaderMap::new();
-    headers.insert("X-Api-Key", HeaderValue::from_str(&api_token).unwrap());
-    headers.insert("Content-Type", HeaderValue::from_static("application/json"));
-
-    let language = get_locale().unwrap_or_else(|| "pt-BR".to_string());
+    let language = get_locale().unwrap_or_else(|| config.language_fallback().to_string());
 
-    let body = json!({
-        "inputs": {
-            "diff": output,
-            "language": language,
+    if cli.split {
+        if let Some(mut sp) = sp {
+            sp.stop();
         }
-    });
 
-    let response = client
-        .post("https://sai-library.saiapplications.com/api/templates/66b12119075c349831386040/execute")
-        .headers(headers)
-        .json(&body)
-        .send()
+        let notify = cli.notify.then_some((&client, &config.notify));
+        let open_pr = cli.open_pr.then_some((&client, &config.forge));
+
+        if split::run(
+            &backend,
+            &providers,
+            output,
+            &language,
+            dry_run,
+            cli.force,
+            cli.review,
+            notify,
+            open_pr,
+        )
         .await
-        .expect("Request failed");
+        .is_err()
+        {
+            std::process::exit(1);
+        }
 
-    let commit_msg = response.text().await.expect("Couldn't parse response");
-    
-    if sp.is_some() {
-        sp.unwrap().stop_with_message("Finished Analyzing!".into());
+        return Ok(());
+    }
+
+    let mut generated = None;
+    for provider in &providers {
+        match provider.generate(output, &language).await {
+            Ok(commit) => {
+                generated = Some(commit);
+                break;
+            }
+            Err(err) => {
+                warn!("Provider '{}' skipped: {}", provider.name(), err);
+            }
+        }
+    }
+
+    let commit_msg = generated
+        .unwrap_or_else(|| {
+            error!("All configured providers failed to generate a commit message.");
+            std::process::exit(1);
+        })
+        .to_string();
+
+    if let Some(mut sp) = sp {
+        sp.stop_with_message("Finished Analyzing!".into());
     }
 
-    if cli.dry_run {
+    if dry_run {
         info!("{}", commit_msg);
         return Ok(());
     } else {
@@ -198,27 +228,110 @@ async fn main() -> Result<(), ()> {
         }
     }
 
-    let mut ps_commit = Command::new("git")
-        .arg("commit")
-        .args(if cli.review { vec!["-e"] } else { vec![] })
-        .arg("-F")
-        .arg("-")
-        .stdin(Stdio::piped())
-        .spawn()
-        .unwrap();
-
-    let mut stdin = ps_commit.stdin.take().expect("Failed to open stdin");
-    std::thread::spawn(move || {
-        stdin
-            .write_all(commit_msg.as_bytes())
-            .expect("Failed to write to stdin");
+    let commit_msg = if cli.review {
+        edit_message(&commit_msg).unwrap_or(commit_msg)
+    } else {
+        commit_msg
+    };
+
+    let hash = backend.commit(&commit_msg).unwrap_or_else(|err| {
+        error!("There was an error when creating the commit: {}", err);
+        std::process::exit(1);
     });
 
-    let commit_output = ps_commit
-        .wait_with_output()
-        .expect("There was an error when creating the commit.");
+    info!("Created commit {}", &hash[..7]);
 
-    info!("{}", str::from_utf8(&commit_output.stdout).unwrap());
+    if cli.notify {
+        if let Some(notifier) = Notifier::from_config(&config.notify) {
+            let author = backend.author().unwrap_or_else(|_| "unknown".to_string());
+            if let Err(err) = notifier
+                .notify(&client, &hash[..7], &author, &commit_msg, output)
+                .await
+            {
+                error!("Couldn't send notification: {}", err);
+            }
+        } else {
+            warn!("--notify was passed but no recipients are configured under [notify].");
+        }
+    }
+
+    if cli.open_pr {
+        match Forge::from_config(&client, &config.forge) {
+            Some(Ok(forge)) => match backend.push_current_branch(config.forge.remote()) {
+                Ok(branch) => {
+                    let title = commit_msg.lines().next().unwrap_or(&commit_msg);
+                    match forge.report(&branch, &hash, title, &commit_msg).await {
+                        Ok(url) => info!("Reported commit upstream: {}", url),
+                        Err(err) => error!("Couldn't report commit to the forge: {}", err),
+                    }
+                }
+                Err(err) => error!("Couldn't push branch: {}", err),
+            },
+            Some(Err(err)) => error!("Couldn't report commit to the forge: {}", err),
+            None => warn!("--open-pr was passed but no [forge] owner/repo are configured."),
+        }
+    }
 
     Ok(())
 }
+
+/// Builds the ordered provider list from `config.providers`, skipping any
+/// entry whose required API key isn't set (with a warning) instead of
+/// failing the whole run.
+fn build_providers(config: &Config, client: &Client) -> Vec<Box<dyn Provider>> {
+    let mut providers: Vec<Box<dyn Provider>> = Vec::new();
+
+    for name in config.providers() {
+        match name.as_str() {
+            "sai" => match std::env::var(config.api_key_env()) {
+                Ok(api_token) => providers.push(Box::new(SaiProvider::new(
+                    client.clone(),
+                    api_token,
+                    config.execute_url(),
+                ))),
+                Err(_) => warn!(
+                    "Skipping 'sai' provider: {} is not set.",
+                    config.api_key_env()
+                ),
+            },
+            "openai" => match std::env::var(config.openai_api_key_env()) {
+                Ok(api_key) => providers.push(Box::new(OpenAiProvider::new(
+                    client.clone(),
+                    api_key,
+                    config.openai_base_url().to_string(),
+                    config.openai_model().to_string(),
+                ))),
+                Err(_) => warn!(
+                    "Skipping 'openai' provider: {} is not set.",
+                    config.openai_api_key_env()
+                ),
+            },
+            other => warn!("Unknown provider '{}' in config, skipping.", other),
+        }
+    }
+
+    providers
+}
+
+/// Opens the message in the user's `$EDITOR` (falling back to `vi`) and
+/// returns the edited contents, mirroring what `git commit -e` used to do
+/// for us before commits were created directly through `git2`.
+pub(crate) fn edit_message(message: &str) -> Result<String, ()> {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    let path = std::env::temp_dir().join("auto-commit-MSG");
+
+    std::fs::write(&path, message).map_err(|_| ())?;
+
+    let status = Command::new(editor)
+        .arg(&path)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .status()
+        .map_err(|_| ())?;
+
+    if !status.success() {
+        return Err(());
+    }
+
+    std::fs::read_to_string(&path).map_err(|_| ())
+}
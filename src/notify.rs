@@ -0,0 +1,113 @@
+use crate::config::NotifyConfig;
+use reqwest::Client;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+enum Transport<'a> {
+    Sendmail { binary: &'a str },
+    Http { url: &'a str },
+}
+
+/// Emails the commit (hash, author, subject/body, diff) to the recipients
+/// configured under `[notify]`, once the commit has been created. Gated
+/// behind `--notify` so it's opt-in.
+pub struct Notifier<'a> {
+    from: &'a str,
+    recipients: &'a [String],
+    transport: Transport<'a>,
+}
+
+impl<'a> Notifier<'a> {
+    /// Builds a notifier from config, or `None` if no recipients are set.
+    pub fn from_config(config: &'a NotifyConfig) -> Option<Self> {
+        if config.recipients.is_empty() {
+            return None;
+        }
+
+        let transport = match config.transport.as_deref() {
+            Some("http") => Transport::Http {
+                url: config.http_url.as_deref()?,
+            },
+            _ => Transport::Sendmail {
+                binary: config.sendmail_binary(),
+            },
+        };
+
+        Some(Self {
+            from: config.from.as_deref().unwrap_or("auto-commit@localhost"),
+            recipients: &config.recipients,
+            transport,
+        })
+    }
+
+    pub async fn notify(
+        &self,
+        client: &Client,
+        hash: &str,
+        author: &str,
+        message: &str,
+        diff: &str,
+    ) -> Result<(), String> {
+        let email = render_rfc822(self.from, self.recipients, hash, author, message, diff);
+
+        match &self.transport {
+            Transport::Sendmail { binary } => send_via_sendmail(binary, &email),
+            Transport::Http { url } => send_via_http(client, url, &email).await,
+        }
+    }
+}
+
+fn render_rfc822(
+    from: &str,
+    recipients: &[String],
+    hash: &str,
+    author: &str,
+    message: &str,
+    diff: &str,
+) -> String {
+    let subject = message.lines().next().unwrap_or(hash);
+
+    format!(
+        "From: {from}\r\nTo: {to}\r\nSubject: [auto-commit] {subject}\r\n\r\ncommit {hash}\r\nAuthor: {author}\r\n\r\n{message}\r\n\r\n{diff}\r\n",
+        to = recipients.join(", "),
+    )
+}
+
+fn send_via_sendmail(binary: &str, email: &str) -> Result<(), String> {
+    let mut child = Command::new(binary)
+        .arg("-t")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("couldn't spawn '{binary}': {err}"))?;
+
+    child
+        .stdin
+        .take()
+        .expect("piped stdin")
+        .write_all(email.as_bytes())
+        .map_err(|err| format!("couldn't write to '{binary}' stdin: {err}"))?;
+
+    let status = child
+        .wait()
+        .map_err(|err| format!("'{binary}' failed: {err}"))?;
+
+    if !status.success() {
+        return Err(format!("'{binary}' exited with {status}"));
+    }
+
+    Ok(())
+}
+
+async fn send_via_http(client: &Client, url: &str, email: &str) -> Result<(), String> {
+    client
+        .post(url)
+        .header("Content-Type", "message/rfc822")
+        .body(email.to_string())
+        .send()
+        .await
+        .map_err(|err| format!("notification POST to {url} failed: {err}"))?
+        .error_for_status()
+        .map_err(|err| format!("notification POST to {url} failed: {err}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+mod openai;
+mod sai;
+
+pub use openai::OpenAiProvider;
+pub use sai::SaiProvider;
+
+use crate::commit::{Commit, SplitPlan};
+use async_trait::async_trait;
+use thiserror::Error;
+
+/// Why a provider didn't produce a commit, so the fallback loop in `main`
+/// can log a useful reason before moving on to the next one.
+#[derive(Debug, Error)]
+pub enum ProviderError {
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+/// A backend capable of turning a diff into a commit message. Implementors
+/// are tried in the order configured in `providers`, and a failure on one
+/// just moves on to the next.
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// Short identifier used in logs (e.g. `"sai"`, `"openai"`).
+    fn name(&self) -> &str;
+
+    async fn generate(&self, diff: &str, language: &str) -> Result<Commit, ProviderError>;
+
+    /// Proposes a breakdown of `diff` (covering `files`) into several
+    /// semantic commits, for `--split`.
+    async fn propose_split(
+        &self,
+        diff: &str,
+        files: &[String],
+        language: &str,
+    ) -> Result<SplitPlan, ProviderError>;
+}
@@ -0,0 +1,144 @@
+use super::{Provider, ProviderError};
+use crate::commit::{Commit, SplitPlan};
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderValue},
+    Client,
+};
+use serde_json::json;
+
+/// Talks to any OpenAI-compatible `/chat/completions` endpoint, so users can
+/// point this at a local model (e.g. Ollama, vLLM) when the hosted SAI API
+/// is unavailable.
+pub struct OpenAiProvider {
+    client: Client,
+    api_key: String,
+    base_url: String,
+    model: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(client: Client, api_key: String, base_url: String, model: String) -> Self {
+        Self {
+            client,
+            api_key,
+            base_url,
+            model,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for OpenAiProvider {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    async fn generate(&self, diff: &str, language: &str) -> Result<Commit, ProviderError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str(&format!("Bearer {}", self.api_key))
+                .map_err(|err| ProviderError::Other(err.to_string()))?,
+        );
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+
+        let body = json!({
+            "model": self.model,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": format!(
+                        "You write git commit messages in {language}. Reply with just the commit message."
+                    ),
+                },
+                {
+                    "role": "user",
+                    "content": diff,
+                }
+            ],
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "commit",
+                    "schema": Commit::json_schema(),
+                    "strict": true,
+                },
+            },
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .headers(headers)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let payload: serde_json::Value = response.json().await?;
+        let text = payload["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| ProviderError::Other("missing choices[0].message.content".into()))?;
+
+        Ok(Commit::parse_or_raw(text))
+    }
+
+    async fn propose_split(
+        &self,
+        diff: &str,
+        files: &[String],
+        language: &str,
+    ) -> Result<SplitPlan, ProviderError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str(&format!("Bearer {}", self.api_key))
+                .map_err(|err| ProviderError::Other(err.to_string()))?,
+        );
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+
+        let body = json!({
+            "model": self.model,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": format!(
+                        "You split a git diff into logically separate commits, each with its \
+                         own message in {language}. The changed files are: {}.",
+                        files.join(", ")
+                    ),
+                },
+                {
+                    "role": "user",
+                    "content": diff,
+                }
+            ],
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "split_plan",
+                    "schema": SplitPlan::json_schema(),
+                    "strict": true,
+                },
+            },
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .headers(headers)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let payload: serde_json::Value = response.json().await?;
+        let text = payload["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| ProviderError::Other("missing choices[0].message.content".into()))?;
+
+        SplitPlan::parse(text)
+            .map_err(|err| ProviderError::Other(format!("couldn't parse split plan: {err}")))
+    }
+}
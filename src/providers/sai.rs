@@ -0,0 +1,102 @@
+use super::{Provider, ProviderError};
+use crate::commit::{Commit, SplitPlan};
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderValue},
+    Client,
+};
+use serde_json::json;
+
+/// Talks to the hosted SAI template execution API.
+pub struct SaiProvider {
+    client: Client,
+    api_token: String,
+    execute_url: String,
+}
+
+impl SaiProvider {
+    pub fn new(client: Client, api_token: String, execute_url: String) -> Self {
+        Self {
+            client,
+            api_token,
+            execute_url,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for SaiProvider {
+    fn name(&self) -> &str {
+        "sai"
+    }
+
+    async fn generate(&self, diff: &str, language: &str) -> Result<Commit, ProviderError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Api-Key",
+            HeaderValue::from_str(&self.api_token)
+                .map_err(|err| ProviderError::Other(err.to_string()))?,
+        );
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+
+        let body = json!({
+            "inputs": {
+                "diff": diff,
+                "language": language,
+            },
+            "response_schema": Commit::json_schema(),
+        });
+
+        let response = self
+            .client
+            .post(&self.execute_url)
+            .headers(headers)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let text = response.text().await?;
+
+        Ok(Commit::parse_or_raw(&text))
+    }
+
+    async fn propose_split(
+        &self,
+        diff: &str,
+        files: &[String],
+        language: &str,
+    ) -> Result<SplitPlan, ProviderError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Api-Key",
+            HeaderValue::from_str(&self.api_token)
+                .map_err(|err| ProviderError::Other(err.to_string()))?,
+        );
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+
+        let body = json!({
+            "inputs": {
+                "diff": diff,
+                "files": files,
+                "language": language,
+                "mode": "split",
+            },
+            "response_schema": SplitPlan::json_schema(),
+        });
+
+        let response = self
+            .client
+            .post(&self.execute_url)
+            .headers(headers)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let text = response.text().await?;
+
+        SplitPlan::parse(&text)
+            .map_err(|err| ProviderError::Other(format!("couldn't parse split plan: {err}")))
+    }
+}
@@ -0,0 +1,155 @@
+use crate::config::{ForgeConfig, NotifyConfig};
+use crate::edit_message;
+use crate::forge::Forge;
+use crate::git::GitBackend;
+use crate::notify::Notifier;
+use crate::providers::Provider;
+use log::{error, info, warn};
+use question::{Answer, Question};
+use reqwest::Client;
+
+/// Walks the staged diff file-by-file, asks the providers to propose a
+/// logical grouping into several commits, then creates one commit per
+/// group — applying the same confirm/`--review`/`--force` flow as a
+/// regular single commit.
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    backend: &GitBackend,
+    providers: &[Box<dyn Provider>],
+    diff: &str,
+    language: &str,
+    dry_run: bool,
+    force: bool,
+    review: bool,
+    notify: Option<(&Client, &NotifyConfig)>,
+    open_pr: Option<(&Client, &ForgeConfig)>,
+) -> Result<(), ()> {
+    let files = backend.staged_files().map_err(|err| {
+        error!("Couldn't list staged files: {}", err);
+    })?;
+
+    let mut plan = None;
+    for provider in providers {
+        match provider.propose_split(diff, &files, language).await {
+            Ok(proposed) => {
+                plan = Some(proposed);
+                break;
+            }
+            Err(err) => {
+                warn!("Provider '{}' skipped: {}", provider.name(), err);
+            }
+        }
+    }
+
+    let plan = plan.ok_or_else(|| {
+        error!("All configured providers failed to propose a split.");
+    })?;
+
+    if plan.groups.is_empty() {
+        error!("No commit groups were proposed.");
+        return Err(());
+    }
+
+    let mut created = Vec::new();
+
+    for (index, group) in plan.groups.iter().enumerate() {
+        let message = group.commit.to_string();
+
+        info!(
+            "Commit {}/{} ({} file(s)):\n------------------------------\n{}\n------------------------------",
+            index + 1,
+            plan.groups.len(),
+            group.paths.len(),
+            message
+        );
+
+        if dry_run {
+            continue;
+        }
+
+        if !force {
+            let answer = Question::new("Do you want to create this commit? (Y/n)")
+                .yes_no()
+                .until_acceptable()
+                .default(Answer::YES)
+                .ask()
+                .expect("Couldn't ask question.");
+
+            if answer == Answer::NO {
+                info!("Skipped.");
+                continue;
+            }
+        }
+
+        let message = if review {
+            edit_message(&message).unwrap_or(message)
+        } else {
+            message
+        };
+
+        backend.restage_only(&group.paths).map_err(|err| {
+            error!("Couldn't stage {:?}: {}", group.paths, err);
+        })?;
+
+        let group_diff = backend.staged_diff().map_err(|err| {
+            error!("Couldn't compute the diff for {:?}: {}", group.paths, err);
+        })?;
+
+        let hash = backend.commit(&message).map_err(|err| {
+            error!("There was an error when creating the commit: {}", err);
+        })?;
+
+        if let Some((client, notify_config)) = notify {
+            if let Some(notifier) = Notifier::from_config(notify_config) {
+                let author = backend.author().unwrap_or_else(|_| "unknown".to_string());
+                if let Err(err) = notifier
+                    .notify(client, &hash[..7], &author, &message, &group_diff)
+                    .await
+                {
+                    error!("Couldn't send notification for {}: {}", &hash[..7], err);
+                }
+            }
+        }
+
+        created.push((hash, group.commit.title.clone(), group.commit.description.clone()));
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    info!("Created {} commit(s):", created.len());
+    for (hash, title, _) in &created {
+        info!("  {} {}", &hash[..7], title);
+    }
+
+    if let Some((client, forge_config)) = open_pr {
+        if created.is_empty() {
+            warn!("--open-pr was passed but no commits were created.");
+        } else {
+            match Forge::from_config(client, forge_config) {
+                Some(Ok(forge)) => match backend.push_current_branch(forge_config.remote()) {
+                    Ok(branch) => {
+                        let (hash, title, description) =
+                            created.last().expect("created is non-empty");
+                        let summary = created
+                            .iter()
+                            .map(|(hash, title, _)| format!("{} {}", &hash[..7], title))
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        let body = format!("{summary}\n\n{description}");
+                        match forge.report(&branch, hash, title, &body).await {
+                            Ok(url) => info!("Reported commit upstream: {}", url),
+                            Err(err) => error!("Couldn't report commit to the forge: {}", err),
+                        }
+                    }
+                    Err(err) => error!("Couldn't push branch: {}", err),
+                },
+                Some(Err(err)) => error!("Couldn't report commit to the forge: {}", err),
+                None => warn!("--open-pr was passed but no [forge] owner/repo are configured."),
+            }
+        }
+    }
+
+    Ok(())
+}